@@ -1,46 +1,249 @@
+mod metrics;
+
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
 use chrono::Local;
 use clap::{ArgAction, Parser};
-use regex::RegexBuilder;
+use metrics::{compute_file_metrics, render_metrics_table, FileMetrics};
+use regex::{Regex, RegexBuilder};
 use std::{
     fs::File,
     io::{BufReader, BufWriter, Read, Write},
+    path::Path,
     str::FromStr,
 };
 
+/// A single `-a`/`--assignment-files` value. As parsed from the CLI, `paths` holds
+/// the raw, unexpanded tokens (explicit paths, directories, globs); call
+/// `expand_assignment_files` after `Args::parse()` to turn it into the concrete file
+/// list `write_assignments`/`collect_headings` expect.
 #[derive(Debug, Clone)]
 struct AssignmentFiles {
     paths: Vec<String>,
 }
 
+/// Characters that mark a path argument as a glob pattern rather than an explicit path.
+const GLOB_METACHARS: [char; 2] = ['*', '?'];
+
+fn is_glob_pattern(s: &str) -> bool {
+    s.chars().any(|c| GLOB_METACHARS.contains(&c))
+}
+
+/// Translates a glob pattern into an anchored regex, scanning left-to-right so that
+/// the longer `**/`/`**` tokens are matched before the single-character `*` falls back.
+fn glob_to_regex(pattern: &str) -> Result<Regex, String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut translated = String::from("^");
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '*' && chars.get(i + 1) == Some(&'*') && chars.get(i + 2) == Some(&'/') {
+            translated.push_str("(?:.*/)?");
+            i += 3;
+        } else if chars[i] == '*' && chars.get(i + 1) == Some(&'*') {
+            translated.push_str(".*");
+            i += 2;
+        } else if chars[i] == '*' {
+            translated.push_str("[^/]*");
+            i += 1;
+        } else if chars[i] == '?' {
+            translated.push_str("[^/]");
+            i += 1;
+        } else if chars[i] == '.' {
+            translated.push_str("\\.");
+            i += 1;
+        } else if "()[]{}+-|^$\\&~# \t".contains(chars[i]) {
+            translated.push('\\');
+            translated.push(chars[i]);
+            i += 1;
+        } else {
+            translated.push(chars[i]);
+            i += 1;
+        }
+    }
+    translated.push('$');
+
+    RegexBuilder::new(&translated)
+        .build()
+        .map_err(|e| format!("{} is not a valid glob pattern: {}", pattern, e))
+}
+
+/// Recursively walks `dir`, appending every regular file path found, in the order
+/// `read_dir` yields them (the caller is expected to sort afterwards for determinism).
+fn collect_files_recursively(dir: &Path, out: &mut Vec<String>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("could not read directory {}: {}", dir.display(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("could not read entry in {}: {}", dir.display(), e))?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files_recursively(&path, out)?;
+        } else {
+            out.push(path.to_string_lossy().into_owned());
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps only the files that `write_assignments` would actually be able to render,
+/// then sorts the result so output is stable across runs regardless of filesystem
+/// iteration order. This mirrors the explicit-path policy exactly: a file is dropped
+/// only when `get_escape_expression_from_file_extension` would error for it (no
+/// extension at all) and it isn't a recognized media attachment — an unmapped
+/// extension like `.txt` is kept and falls back to an unlabeled fence, the same as it
+/// would if listed explicitly, rather than being silently dropped here but accepted
+/// there. Expansion runs after `Args::parse()` (see `expand_assignment_files`), so
+/// `lang_map` is available here the same way it is at render time.
+fn filter_recognized_and_sort(mut files: Vec<String>, lang_map: &[LangMapEntry]) -> Vec<String> {
+    files.retain(|path| {
+        let file_name = get_file_name_from_path(path);
+        if is_media_ext(file_name) {
+            return true;
+        }
+        get_escape_expression_from_file_extension(file_name, path, lang_map).is_ok()
+    });
+    files.sort();
+    files
+}
+
+/// Rewrites `\` separators to `/` so a Windows drive path (`C:\src\main.c`) behaves
+/// the same as a Unix-style one; `Path`/`PathBuf` accept `/` on both platforms, so
+/// this is enough to canonicalize them. Scoped to Windows builds only: on Unix `\` is
+/// a legal filename character, and rewriting it there would corrupt a real path
+/// instead of normalizing one. This doesn't fix a space inside a Windows path (e.g.
+/// `C:\Program Files\main.c`) — see the split-on-space note on
+/// `AssignmentFiles::from_str`.
+#[cfg(target_os = "windows")]
+fn normalize_path_separators(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+#[cfg(not(target_os = "windows"))]
+fn normalize_path_separators(path: &str) -> String {
+    String::from(path)
+}
+
+/// Expands a single CLI path argument into the concrete list of files it refers to:
+/// a directory is walked recursively, a glob pattern is matched against the files
+/// under its non-glob base directory, and anything else is kept as an explicit path.
+fn expand_path_argument(raw_path: &str, lang_map: &[LangMapEntry]) -> Result<Vec<String>, String> {
+    let path = normalize_path_separators(raw_path);
+    let path = path.as_str();
+
+    if is_glob_pattern(path) {
+        let base = path
+            .split('/')
+            .take_while(|component| !is_glob_pattern(component))
+            .collect::<Vec<_>>()
+            .join("/");
+        let base = if base.is_empty() { "." } else { base.as_str() };
+
+        let regex = glob_to_regex(path)?;
+
+        let mut candidates = Vec::new();
+        collect_files_recursively(Path::new(base), &mut candidates)?;
+
+        // `collect_files_recursively(".")` yields `./top.c`, but the pattern itself
+        // (e.g. `*.c`) has no `./` prefix to match against; strip it here so a plain
+        // glob rooted at the current directory matches the same way `**/*.c` does.
+        if base == "." {
+            for candidate in &mut candidates {
+                if let Some(stripped) = candidate.strip_prefix("./") {
+                    *candidate = String::from(stripped);
+                }
+            }
+        }
+
+        let matched: Vec<String> = candidates
+            .into_iter()
+            .filter(|candidate| regex.is_match(candidate))
+            .collect();
+
+        let matched = filter_recognized_and_sort(matched, lang_map);
+        if matched.is_empty() {
+            return Err(format!("{} did not match any recognized file.", path));
+        }
+
+        return Ok(matched);
+    }
+
+    if Path::new(path).is_dir() {
+        let mut files = Vec::new();
+        collect_files_recursively(Path::new(path), &mut files)?;
+
+        let files = filter_recognized_and_sort(files, lang_map);
+        if files.is_empty() {
+            return Err(format!("{} does not contain any recognized file.", path));
+        }
+
+        return Ok(files);
+    }
+
+    if path.is_empty() || Path::new(path).file_name().is_none() {
+        return Err(format!("{} is not a valid path.", path));
+    }
+
+    Ok(vec![String::from(path)])
+}
+
+/// Expands every raw `AssignmentFiles` group parsed from the CLI (directories and
+/// globs included) into concrete file lists, now that `args.lang_map` is available.
+/// This runs after `Args::parse()` rather than inside `AssignmentFiles::from_str`,
+/// since `--lang-map` is itself a separate, independently-parsed CLI argument.
+fn expand_assignment_files(
+    raw_assignment_files: &[AssignmentFiles],
+    lang_map: &[LangMapEntry],
+) -> Result<Vec<AssignmentFiles>, String> {
+    raw_assignment_files
+        .iter()
+        .map(|group| {
+            let mut paths = Vec::new();
+            for raw_path in &group.paths {
+                paths.extend(expand_path_argument(raw_path, lang_map)?);
+            }
+            Ok(AssignmentFiles { paths })
+        })
+        .collect()
+}
+
 impl FromStr for AssignmentFiles {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let as_chars: Vec<char> = s.chars().collect();
+        // NOTE: paths are split on every single space character, and each `-a` value
+        // goes through this same split, so a path containing a space (e.g. a Windows
+        // `C:\Program Files\main.c`) cannot be expressed at all.
+        let paths: Vec<String> = s.split(' ').map(String::from).collect();
 
-        let s = String::from_iter(as_chars.as_slice().iter());
-        let paths: Vec<&str> = s.split(' ').collect();
-
-        if paths.len() == 0 {
+        if paths.is_empty() {
             return Err(String::from("No file paths have been supplied."));
         }
 
-        let regex = RegexBuilder::new("(\\/?[^:/\0]+)+").build().unwrap();
+        Ok(Self { paths })
+    }
+}
 
-        let mut v: Vec<String> = Vec::new();
+#[derive(Debug, Clone)]
+struct LangMapEntry {
+    extension: String,
+    fence: String,
+}
 
-        for path in &paths {
-            let captures = regex.captures(path);
-            if let None = captures {
-                return Err(format!("{} is not a valid path", *path));
-            }
-            let captures = captures.unwrap();
-            if *path != captures.get_match().as_str() {
-                return Err(format!("{} is not a valid path.", path));
-            }
-            v.push(String::from(*path));
+impl FromStr for LangMapEntry {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (extension, fence) = s
+            .split_once('=')
+            .ok_or_else(|| format!("{} is not in the form ext=fence", s))?;
+
+        if extension.is_empty() || fence.is_empty() {
+            return Err(format!("{} is not in the form ext=fence", s));
         }
 
-        Ok(Self { paths: v })
+        Ok(Self {
+            extension: extension.to_lowercase(),
+            fence: String::from(fence),
+        })
     }
 }
 
@@ -67,6 +270,22 @@ struct Args {
 
     #[arg(short, long)]
     output_file: Option<String>,
+
+    /// Registers or overrides an extension-to-fence mapping, e.g. `--lang-map rs=rust`.
+    #[arg(long, value_parser = clap::value_parser!(LangMapEntry), action = ArgAction::Append)]
+    lang_map: Vec<LangMapEntry>,
+
+    /// Inlines image attachments as base64 `data:` URLs instead of linking to the path.
+    #[arg(long)]
+    embed_media: bool,
+
+    /// Appends a per-assignment Markdown table of SLOC/comment/complexity metrics.
+    #[arg(long)]
+    metrics: bool,
+
+    /// Emits a linked table of contents before the assignment bodies.
+    #[arg(long)]
+    toc: bool,
 }
 
 fn write_header_on_file(
@@ -96,51 +315,226 @@ fn write_header_on_file(
 }
 
 fn get_file_name_from_path(path: &str) -> &str {
-    let regex = RegexBuilder::new("([^:/\0]+)+").build().unwrap();
-    regex.find_iter(path).last().unwrap().as_str()
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path)
+}
+
+/// Default extension-to-fence mapping for the languages assignments commonly use.
+/// `--lang-map` entries are consulted first and take precedence over this table.
+const DEFAULT_LANG_MAP: &[(&str, &str)] = &[
+    ("c", "c"),
+    ("h", "c"),
+    ("cpp", "cpp"),
+    ("hpp", "cpp"),
+    ("cc", "cpp"),
+    ("cxx", "cpp"),
+    ("rs", "rust"),
+    ("py", "python"),
+    ("java", "java"),
+    ("js", "javascript"),
+    ("jsx", "javascript"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("go", "go"),
+    ("sql", "sql"),
+    ("sh", "bash"),
+    ("bash", "bash"),
+    ("hs", "haskell"),
+];
+
+/// Returns everything after the final `.` in `file`, regardless of its length.
+fn get_file_extension(file: &str) -> Option<&str> {
+    file.rsplit_once('.').map(|(_, extension)| extension)
+}
+
+/// Image extensions emitted as an inline Markdown `![]()` reference.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+/// Non-image attachment extensions that still belong on the media path (a plain
+/// Markdown link) rather than a code fence. A PDF can't usefully be inlined as a
+/// `![]()` image (browsers don't render a `data:` PDF there), so it is always linked,
+/// even when `--embed-media` is set.
+const LINKED_MEDIA_EXTENSIONS: &[&str] = &["pdf"];
+
+/// Mirrors `get_escape_expression_from_file_extension`'s extension lookup, but for the
+/// media path: keeps the fence path and the image/attachment path cleanly separated.
+fn is_media_ext(file: &str) -> bool {
+    get_file_extension(file)
+        .map(|extension| {
+            let extension = extension.to_lowercase();
+            IMAGE_EXTENSIONS.contains(&extension.as_str())
+                || LINKED_MEDIA_EXTENSIONS.contains(&extension.as_str())
+        })
+        .unwrap_or(false)
 }
 
+fn is_image_ext(file: &str) -> bool {
+    get_file_extension(file)
+        .map(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Maps an image extension to the MIME type used for `--embed-media` data URLs.
+fn mime_type_for_media_ext(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Resolves the fence label for `file`'s extension, consulting `lang_map` overrides
+/// before falling back to `DEFAULT_LANG_MAP`. Returns `Ok(None)` for an unrecognized
+/// extension so the caller can fall back to a plain, unlabeled fence instead of
+/// aborting the whole run; only the total absence of an extension is an error.
 fn get_escape_expression_from_file_extension(
     file: &str,
     file_path: &str,
-) -> Result<&'static str, Box<dyn std::error::Error>> {
-    let extension_regex = RegexBuilder::new("\\.[^:/\0]{1,3}").build()?;
-    if let Some(r) = extension_regex.find_iter(file).last() {
-        match r.as_str() {
-            ".c" | ".h" => Ok("c"),
-            ".cpp" | ".hpp" => Ok("cpp"),
-            _ => Err(format!("Unsupported extension {}.", r.as_str()).into()),
-        }
-    } else {
-        Err(format!(
+    lang_map: &[LangMapEntry],
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let extension = get_file_extension(file).ok_or_else(|| {
+        format!(
             "No extension was found for file with name '{}' on path '{}'.",
             file, file_path
         )
-        .into())
+    })?;
+    let extension = extension.to_lowercase();
+
+    if let Some(entry) = lang_map.iter().find(|entry| entry.extension == extension) {
+        return Ok(Some(entry.fence.clone()));
+    }
+
+    Ok(DEFAULT_LANG_MAP
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, fence)| fence.to_string()))
+}
+
+fn assignment_heading(args: &Args, index: usize) -> String {
+    if args.tutorial {
+        format!("Tutorial {}", index + 1)
+    } else {
+        format!("Assignment {}", index + 1)
+    }
+}
+
+/// Enumerates every `## Assignment N`/`## Tutorial N` and `### File:` heading that
+/// `write_assignments` will emit, paired with its Markdown heading level, so a table
+/// of contents can be built before any bytes are streamed to the output file.
+fn collect_headings(args: &Args, assignment_files: &[AssignmentFiles]) -> Vec<(u8, String)> {
+    let mut headings = Vec::new();
+
+    for (i, assignment_files) in assignment_files.iter().enumerate() {
+        headings.push((2, assignment_heading(args, i)));
+
+        for path in &assignment_files.paths {
+            let file_name = get_file_name_from_path(path);
+            headings.push((3, format!("File: {}", file_name)));
+        }
+    }
+
+    headings
+}
+
+/// Builds a GitHub-style slug anchor: lowercased, spaces turned into hyphens, and
+/// all punctuation other than hyphens and underscores stripped.
+fn slugify_heading(heading: &str) -> String {
+    let mut slug = String::new();
+    let mut last_was_hyphen = false;
+
+    for c in heading.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            slug.push(c.to_ascii_lowercase());
+            last_was_hyphen = false;
+        } else if (c == ' ' || c == '-') && !last_was_hyphen && !slug.is_empty() {
+            slug.push('-');
+            last_was_hyphen = true;
+        }
+    }
+
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+
+    slug
+}
+
+/// Renders the linked table of contents block, indenting `### File:` entries under
+/// their parent `## Assignment`/`## Tutorial` entry.
+fn render_toc(headings: &[(u8, String)]) -> String {
+    let mut toc = String::from("## Table of Contents  \n  \n");
+    let mut slug_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+
+    for (level, text) in headings {
+        let indent = "  ".repeat((*level as usize).saturating_sub(2));
+        let base_slug = slugify_heading(text);
+
+        // Mirror GitHub's anchor deduplication: the first occurrence of a slug keeps
+        // it bare, later occurrences get a `-1`, `-2`, ... suffix, so repeated
+        // filenames across assignments (e.g. `main.c` every week) don't collide.
+        let count = slug_counts.entry(base_slug.clone()).or_insert(0);
+        let slug = if *count == 0 {
+            base_slug.clone()
+        } else {
+            format!("{}-{}", base_slug, *count)
+        };
+        *count += 1;
+
+        toc.push_str(&format!("{}- [{}](#{})  \n", indent, text, slug));
     }
+
+    toc.push_str("  \n");
+    toc
 }
 
 fn write_assignments(
     buf_writer: &mut BufWriter<File>,
     args: &Args,
+    assignment_files: &[AssignmentFiles],
 ) -> Result<(), Box<dyn std::error::Error>> {
-    for (i, assignment_files) in args.assignment_files.iter().enumerate() {
-        if args.tutorial == true {
-            buf_writer.write(format!("## Tutorial {}  \n  \n", i + 1).as_bytes())?;
-        } else {
-            buf_writer.write(format!("## Assignment {}  \n  \n", i + 1).as_bytes())?;
-        }
+    for (i, assignment_files) in assignment_files.iter().enumerate() {
+        buf_writer.write(format!("## {}  \n  \n", assignment_heading(args, i)).as_bytes())?;
+
+        let mut file_metrics: Vec<(String, FileMetrics)> = Vec::new();
 
         for path in &assignment_files.paths {
             let file_name = get_file_name_from_path(path);
             buf_writer.write(format!("### File: {}  \n  \n", file_name).as_bytes())?;
-            buf_writer.write(
-                format!(
-                    "```{}\n",
-                    get_escape_expression_from_file_extension(file_name, path)?
-                )
-                .as_bytes(),
-            )?;
+
+            if is_media_ext(file_name) {
+                if !is_image_ext(file_name) {
+                    // Not an image (e.g. a PDF): always a plain link, since a `data:`
+                    // URL doesn't render inside Markdown's `![]()` image syntax.
+                    buf_writer.write(format!("[{}]({})  \n", file_name, path).as_bytes())?;
+                } else if args.embed_media {
+                    let read_file = File::open(path)?;
+                    let mut buf_reader = BufReader::new(read_file);
+                    let mut v = Vec::new();
+                    buf_reader.read_to_end(&mut v)?;
+
+                    let extension = get_file_extension(file_name)
+                        .unwrap_or_default()
+                        .to_lowercase();
+                    let mime = mime_type_for_media_ext(&extension);
+                    let data = BASE64_STANDARD.encode(&v);
+
+                    buf_writer.write(
+                        format!("![{}](data:{};base64,{})  \n", file_name, mime, data).as_bytes(),
+                    )?;
+                } else {
+                    buf_writer.write(format!("![{}]({})  \n", file_name, path).as_bytes())?;
+                }
+                continue;
+            }
+
+            let fence =
+                get_escape_expression_from_file_extension(file_name, path, &args.lang_map)?
+                    .unwrap_or_default();
+            buf_writer.write(format!("```{}\n", fence).as_bytes())?;
 
             let read_file = File::open(path)?;
             let mut buf_reader = BufReader::new(read_file);
@@ -148,6 +542,20 @@ fn write_assignments(
             buf_reader.read_to_end(&mut v)?;
             buf_writer.write(v.as_slice())?;
             buf_writer.write(format!("```  \n").as_bytes())?;
+
+            if args.metrics {
+                let contents = String::from_utf8_lossy(&v);
+                file_metrics.push((
+                    String::from(file_name),
+                    compute_file_metrics(&fence, &contents),
+                ));
+            }
+        }
+
+        if args.metrics && !file_metrics.is_empty() {
+            buf_writer.write(format!("#### Metrics  \n  \n").as_bytes())?;
+            buf_writer.write(render_metrics_table(&file_metrics).as_bytes())?;
+            buf_writer.write(String::from("  \n").as_bytes())?;
         }
     }
 
@@ -157,6 +565,8 @@ fn write_assignments(
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
+    let assignment_files = expand_assignment_files(&args.assignment_files, &args.lang_map)?;
+
     let output_file = if let Some(p) = &args.output_file {
         // p will have size at least one as it will be validated by clap
         p.clone()
@@ -173,7 +583,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut buf_writer = BufWriter::new(file);
 
     write_header_on_file(&mut buf_writer, &args)?;
-    write_assignments(&mut buf_writer, &args)?;
+
+    if args.toc {
+        let headings = collect_headings(&args, &assignment_files);
+        buf_writer.write(render_toc(&headings).as_bytes())?;
+    }
+
+    write_assignments(&mut buf_writer, &args, &assignment_files)?;
 
     Ok(())
 }
@@ -0,0 +1,154 @@
+//! Rough static metrics for embedded assignment files, used to build the
+//! per-assignment summary table gated behind `--metrics`.
+
+/// Line/block comment tokens for a language, used to separate comment lines from SLOC.
+struct CommentTokens {
+    line: &'static str,
+    block_start: Option<&'static str>,
+    block_end: Option<&'static str>,
+}
+
+fn comment_tokens_for_language(language: &str) -> CommentTokens {
+    match language {
+        "c" | "cpp" | "rust" | "java" | "javascript" | "typescript" | "go" => CommentTokens {
+            line: "//",
+            block_start: Some("/*"),
+            block_end: Some("*/"),
+        },
+        "sql" => CommentTokens {
+            line: "--",
+            block_start: Some("/*"),
+            block_end: Some("*/"),
+        },
+        "haskell" => CommentTokens {
+            line: "--",
+            block_start: Some("{-"),
+            block_end: Some("-}"),
+        },
+        "python" | "bash" => CommentTokens {
+            line: "#",
+            block_start: None,
+            block_end: None,
+        },
+        _ => CommentTokens {
+            line: "",
+            block_start: None,
+            block_end: None,
+        },
+    }
+}
+
+/// Branch keywords/operators counted towards the cyclomatic-complexity estimate.
+const COMPLEXITY_KEYWORDS: &[&str] = &["if", "for", "while", "case"];
+const COMPLEXITY_OPERATORS: &[&str] = &["&&", "||", "?"];
+
+#[derive(Debug, Clone, Default)]
+pub struct FileMetrics {
+    pub total_lines: usize,
+    pub sloc: usize,
+    pub comment_lines: usize,
+    pub complexity: usize,
+}
+
+impl FileMetrics {
+    fn add(&mut self, other: &FileMetrics) {
+        self.total_lines += other.total_lines;
+        self.sloc += other.sloc;
+        self.comment_lines += other.comment_lines;
+        self.complexity += other.complexity;
+    }
+}
+
+fn count_complexity(line: &str) -> usize {
+    let mut count = 0;
+
+    for keyword in COMPLEXITY_KEYWORDS {
+        count += line
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| word == keyword)
+            .count();
+    }
+
+    for operator in COMPLEXITY_OPERATORS {
+        count += line.matches(operator).count();
+    }
+
+    count
+}
+
+/// Computes total lines, SLOC, comment lines and a branch-keyword complexity estimate
+/// for `contents`, using `language`'s comment tokens (as resolved by
+/// `get_escape_expression_from_file_extension`) to tell comments from source lines.
+/// An unrecognized or empty `language` treats every non-blank line as SLOC.
+pub fn compute_file_metrics(language: &str, contents: &str) -> FileMetrics {
+    let tokens = comment_tokens_for_language(language);
+    let mut metrics = FileMetrics::default();
+    let mut in_block_comment = false;
+
+    for raw_line in contents.lines() {
+        metrics.total_lines += 1;
+        let line = raw_line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if in_block_comment {
+            metrics.comment_lines += 1;
+            if let Some(end) = tokens.block_end {
+                if line.contains(end) {
+                    in_block_comment = false;
+                }
+            }
+            continue;
+        }
+
+        if let Some(start) = tokens.block_start {
+            if !start.is_empty() && line.starts_with(start) {
+                metrics.comment_lines += 1;
+                if let Some(end) = tokens.block_end {
+                    if !line[start.len()..].contains(end) {
+                        in_block_comment = true;
+                    }
+                }
+                continue;
+            }
+        }
+
+        if !tokens.line.is_empty() && line.starts_with(tokens.line) {
+            metrics.comment_lines += 1;
+            continue;
+        }
+
+        metrics.sloc += 1;
+        metrics.complexity += count_complexity(line);
+    }
+
+    metrics
+}
+
+/// Renders the per-assignment metrics table: one row per file plus a totals row.
+pub fn render_metrics_table(files: &[(String, FileMetrics)]) -> String {
+    let mut totals = FileMetrics::default();
+    for (_, metrics) in files {
+        totals.add(metrics);
+    }
+
+    let mut table = String::new();
+    table.push_str("| File | Lines | SLOC | Comments | Complexity |\n");
+    table.push_str("| --- | --- | --- | --- | --- |\n");
+
+    for (file_name, metrics) in files {
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            file_name, metrics.total_lines, metrics.sloc, metrics.comment_lines, metrics.complexity
+        ));
+    }
+
+    table.push_str(&format!(
+        "| **Total** | {} | {} | {} | {} |\n",
+        totals.total_lines, totals.sloc, totals.comment_lines, totals.complexity
+    ));
+
+    table
+}